@@ -3,22 +3,674 @@ use crate::{
     hci::{
         adapter,
         event::{EventCode, EventPacket},
-        le::{self, random::RAND_LEN, report::AdvertisingReport, MetaEvent, RawMetaEvent},
+        le::{
+            self, random::RAND_LEN, report::AddressType, report::AdvertisingReport, MetaEvent,
+            RawMetaEvent,
+        },
         StreamError,
     },
     le::{
         advertisement::{StaticAdvBuffer, MAX_ADV_LEN},
         advertiser::{Advertiser, AdvertisingParameters},
         report::ReportInfo,
-        scan::ScanParameters,
+        scan::{ExtendedScanParameters, ScanParameters},
     },
-    BoxFuture, Stream,
+    BoxFuture, BTAddress, Stream, RSSI,
 };
 use core::convert::TryFrom;
 use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
 use futures_util::StreamExt;
 
+/// LEMeta subevent code for the LE Extended Advertising Report (Core spec Vol 4, Part E 7.7.65.13).
+const EXTENDED_ADVERTISING_REPORT_SUBEVENT_CODE: u8 = 0x0D;
+/// Number of in-progress extended advertisement reassemblies tracked concurrently. Once this is
+/// exceeded, the entry in slot 0 is evicted to make room (see `FragmentReassembly::slot_for`),
+/// bounding memory use on `no_std` targets.
+const MAX_REASSEMBLY_ENTRIES: usize = 4;
+/// Maximum number of bytes accumulated for a single reassembled extended advertisement. Entries
+/// that would grow past this are flushed as truncated instead of accepting more fragments.
+const MAX_REASSEMBLED_LEN: usize = 1650;
+
+/// Filter policy applied by the controller while advertising, controlling whether the filter
+/// accept list (formerly "white list") restricts scan and/or connection requests. Mirrors the
+/// `Advertising_Filter_Policy` parameter encoding of the LE Set Advertising Parameters command.
+/// Scanning and initiating use the narrower [`ScanFilterPolicy`] instead, since their filter
+/// policies don't have an "accept-all-but-filter-one-side" option.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AdvertisingFilterPolicy {
+    /// Accept scan and connection requests from any device.
+    AcceptAll,
+    /// Accept scan and connection requests only from devices on the filter accept list.
+    FilterAcceptListOnly,
+    /// Accept scan requests from any device, but connection requests only from the filter
+    /// accept list.
+    AcceptAllScanFilterAcceptList,
+    /// Accept scan requests only from the filter accept list, but connection requests from any
+    /// device.
+    FilterAcceptListScanAll,
+}
+impl AdvertisingFilterPolicy {
+    pub fn to_raw(self) -> u8 {
+        match self {
+            AdvertisingFilterPolicy::AcceptAll => 0x00,
+            AdvertisingFilterPolicy::FilterAcceptListScanAll => 0x01,
+            AdvertisingFilterPolicy::AcceptAllScanFilterAcceptList => 0x02,
+            AdvertisingFilterPolicy::FilterAcceptListOnly => 0x03,
+        }
+    }
+}
+
+/// Filter policy applied by the controller while scanning or initiating a connection: either
+/// consider every advertiser, or only those on the filter accept list. Unlike
+/// [`AdvertisingFilterPolicy`] there's no "accept-all-but-filter-one-side" option here, so this
+/// is a separate (smaller) type rather than a subset of `AdvertisingFilterPolicy`'s variants —
+/// that keeps `ScanParameters`/`ExtendedScanParameters`/`ConnectionParameters` unable to even
+/// express the advertising-only policies. Mirrors the `Scanning_Filter_Policy` /
+/// `Initiator_Filter_Policy` parameter encodings of the LE Set Scan Parameters / LE Create
+/// Connection commands.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ScanFilterPolicy {
+    /// Accept advertising packets from any device.
+    AcceptAll,
+    /// Accept advertising packets only from devices on the filter accept list.
+    FilterAcceptListOnly,
+}
+impl ScanFilterPolicy {
+    pub fn to_raw(self) -> u8 {
+        match self {
+            ScanFilterPolicy::AcceptAll => 0x00,
+            ScanFilterPolicy::FilterAcceptListOnly => 0x01,
+        }
+    }
+}
+
+/// LEMeta subevent code for the legacy LE Advertising Report.
+const ADVERTISING_REPORT_SUBEVENT_CODE: u8 = 0x02;
+/// Maximum distinct top-level event codes a single [`HciFilter`] can admit.
+const MAX_FILTERED_EVENT_CODES: usize = 4;
+/// Maximum distinct `EventCode::LEMeta` subevent codes a single [`HciFilter`] can admit.
+const MAX_FILTERED_LE_META_SUBEVENTS: usize = 4;
+
+/// Describes which HCI events — and, for [`EventCode::LEMeta`], which subevent codes — a
+/// [`LEAdapter::hci_event_stream`] should deliver. Over a Unix HCI socket transport this is
+/// expected to program the kernel-level filter (`setsockopt(SOL_HCI, HCI_FILTER, ...)`) via
+/// [`adapter::Adapter::set_event_filter`]; `hci_event_stream` also applies it as a host-side
+/// predicate so transports without kernel-level filtering still only yield matching events.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct HciFilter {
+    event_codes: [Option<EventCode>; MAX_FILTERED_EVENT_CODES],
+    le_meta_subevents: [Option<u8>; MAX_FILTERED_LE_META_SUBEVENTS],
+}
+impl HciFilter {
+    /// A filter admitting nothing; build it up with [`HciFilter::allow_event`] and
+    /// [`HciFilter::allow_le_meta_subevent`].
+    pub fn new() -> Self {
+        Self {
+            event_codes: [None; MAX_FILTERED_EVENT_CODES],
+            le_meta_subevents: [None; MAX_FILTERED_LE_META_SUBEVENTS],
+        }
+    }
+    /// Admit events with the given top-level event code.
+    pub fn allow_event(mut self, event_code: EventCode) -> Self {
+        if let Some(slot) = self.event_codes.iter_mut().find(|c| c.is_none()) {
+            *slot = Some(event_code);
+        }
+        self
+    }
+    /// Admit `EventCode::LEMeta` events whose subevent code is `subevent_code`. Implies
+    /// `allow_event(EventCode::LEMeta)`.
+    pub fn allow_le_meta_subevent(self, subevent_code: u8) -> Self {
+        let mut filter = self.allow_event(EventCode::LEMeta);
+        if let Some(slot) = filter
+            .le_meta_subevents
+            .iter_mut()
+            .find(|c| c.is_none())
+        {
+            *slot = Some(subevent_code);
+        }
+        filter
+    }
+    /// Iterates the top-level event codes this filter admits, for transports that program a
+    /// kernel-level filter from it (see `adapter::socket::set_kernel_filter`).
+    pub fn event_codes(&self) -> impl Iterator<Item = EventCode> + '_ {
+        self.event_codes.iter().flatten().copied()
+    }
+    /// Returns whether `event` is admitted by this filter.
+    fn matches<Buf: Storage<u8>>(&self, event: &EventPacket<Buf>) -> bool {
+        if !self
+            .event_codes
+            .iter()
+            .flatten()
+            .any(|code| *code == event.event_code)
+        {
+            return false;
+        }
+        if event.event_code != EventCode::LEMeta || self.le_meta_subevents.iter().all(Option::is_none)
+        {
+            return true;
+        }
+        match RawMetaEvent::try_from(event.as_ref()) {
+            Ok(meta_event) => self
+                .le_meta_subevents
+                .iter()
+                .flatten()
+                .any(|code| *code == meta_event.subevent_code()),
+            Err(_) => false,
+        }
+    }
+}
+impl Default for HciFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mask applied to the most significant byte of a static random address, per the Bluetooth Core
+/// Spec Vol 6, Part B, Section 1.3.2.1: the two most significant bits must be `0b11`.
+const STATIC_RANDOM_ADDRESS_MARKER: u8 = 0b1100_0000;
+
+/// Which address the controller uses as "its own" when advertising, scanning or initiating a
+/// connection. Threaded through [`AdvertisingParameters::own_address_type`] and
+/// [`ScanParameters::own_address_type`] so callers can opt into a random (typically static, see
+/// [`LEAdapter::generate_static_random_address`]) identity instead of the public device address.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum OwnAddressType {
+    Public,
+    Random,
+    /// Resolvable Private Address from the resolving list, falling back to the public address.
+    ResolvablePrivateOrPublic,
+    /// Resolvable Private Address from the resolving list, falling back to the random address.
+    ResolvablePrivateOrRandom,
+}
+impl OwnAddressType {
+    pub fn to_raw(self) -> u8 {
+        match self {
+            OwnAddressType::Public => 0x00,
+            OwnAddressType::Random => 0x01,
+            OwnAddressType::ResolvablePrivateOrPublic => 0x02,
+            OwnAddressType::ResolvablePrivateOrRandom => 0x03,
+        }
+    }
+}
+
+/// AD type codes from the Bluetooth Core Specification Supplement, Part A, Section 1.
+mod ad_type {
+    pub const FLAGS: u8 = 0x01;
+    pub const INCOMPLETE_SERVICE_UUID_16: u8 = 0x02;
+    pub const COMPLETE_SERVICE_UUID_16: u8 = 0x03;
+    pub const INCOMPLETE_SERVICE_UUID_128: u8 = 0x06;
+    pub const COMPLETE_SERVICE_UUID_128: u8 = 0x07;
+    pub const SHORTENED_LOCAL_NAME: u8 = 0x08;
+    pub const COMPLETE_LOCAL_NAME: u8 = 0x09;
+    pub const TX_POWER_LEVEL: u8 = 0x0A;
+    pub const SERVICE_DATA_16: u8 = 0x16;
+    pub const MANUFACTURER_SPECIFIC_DATA: u8 = 0xFF;
+}
+
+/// A single Advertising Data (AD) structure, as defined by the Bluetooth Core Specification
+/// Supplement. Covers the structure types most advertisements need; unrecognized types are
+/// surfaced as [`AdStructure::Unknown`] by the parser rather than being dropped.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AdStructure<'a> {
+    Flags(u8),
+    IncompleteServiceUUID16(&'a [u8]),
+    CompleteServiceUUID16(&'a [u8]),
+    IncompleteServiceUUID128(&'a [u8]),
+    CompleteServiceUUID128(&'a [u8]),
+    ShortenedLocalName(&'a str),
+    CompleteLocalName(&'a str),
+    TxPowerLevel(i8),
+    ManufacturerSpecificData { company_id: u16, data: &'a [u8] },
+    ServiceData16 { uuid: u16, data: &'a [u8] },
+    /// An AD structure whose type this crate doesn't have a dedicated variant for.
+    Unknown { ad_type: u8, data: &'a [u8] },
+}
+impl<'a> AdStructure<'a> {
+    /// Appends this structure's length-type-value encoding to `buf`, returning
+    /// `adapter::Error::BadParameter` if it would overflow `buf`.
+    fn write_to(&self, buf: &mut [u8], len: &mut usize) -> Result<(), adapter::Error> {
+        let (ad_type, value_len): (u8, usize) = match self {
+            AdStructure::Flags(_) => (ad_type::FLAGS, 1),
+            AdStructure::IncompleteServiceUUID16(d) => (ad_type::INCOMPLETE_SERVICE_UUID_16, d.len()),
+            AdStructure::CompleteServiceUUID16(d) => (ad_type::COMPLETE_SERVICE_UUID_16, d.len()),
+            AdStructure::IncompleteServiceUUID128(d) => (ad_type::INCOMPLETE_SERVICE_UUID_128, d.len()),
+            AdStructure::CompleteServiceUUID128(d) => (ad_type::COMPLETE_SERVICE_UUID_128, d.len()),
+            AdStructure::ShortenedLocalName(s) => (ad_type::SHORTENED_LOCAL_NAME, s.len()),
+            AdStructure::CompleteLocalName(s) => (ad_type::COMPLETE_LOCAL_NAME, s.len()),
+            AdStructure::TxPowerLevel(_) => (ad_type::TX_POWER_LEVEL, 1),
+            AdStructure::ManufacturerSpecificData { data, .. } => {
+                (ad_type::MANUFACTURER_SPECIFIC_DATA, 2 + data.len())
+            }
+            AdStructure::ServiceData16 { data, .. } => (ad_type::SERVICE_DATA_16, 2 + data.len()),
+            AdStructure::Unknown { ad_type, data } => (*ad_type, data.len()),
+        };
+        // Structure length byte + type byte + value.
+        let needed = 1 + 1 + value_len;
+        if *len + needed > buf.len() {
+            return Err(adapter::Error::BadParameter);
+        }
+        buf[*len] = (1 + value_len) as u8;
+        buf[*len + 1] = ad_type;
+        let value = &mut buf[*len + 2..*len + needed];
+        match self {
+            AdStructure::Flags(flags) => value[0] = *flags,
+            AdStructure::TxPowerLevel(power) => value[0] = *power as u8,
+            AdStructure::IncompleteServiceUUID16(d)
+            | AdStructure::CompleteServiceUUID16(d)
+            | AdStructure::IncompleteServiceUUID128(d)
+            | AdStructure::CompleteServiceUUID128(d) => value.copy_from_slice(d),
+            AdStructure::ShortenedLocalName(s) | AdStructure::CompleteLocalName(s) => {
+                value.copy_from_slice(s.as_bytes())
+            }
+            AdStructure::ManufacturerSpecificData { company_id, data } => {
+                value[..2].copy_from_slice(&company_id.to_le_bytes());
+                value[2..].copy_from_slice(data);
+            }
+            AdStructure::ServiceData16 { uuid, data } => {
+                value[..2].copy_from_slice(&uuid.to_le_bytes());
+                value[2..].copy_from_slice(data);
+            }
+            AdStructure::Unknown { data, .. } => value.copy_from_slice(data),
+        }
+        *len += needed;
+        Ok(())
+    }
+}
+
+/// Builder that serializes a slice of [`AdStructure`]s into an advertising data payload
+/// suitable for [`LEAdapter::set_advertising_data`].
+pub struct AdvertisingData;
+impl AdvertisingData {
+    /// Serializes `structures` into a buffer of at most [`MAX_ADV_LEN`] (31) bytes.
+    /// # Errors
+    /// Returns `adapter::Error::BadParameter` if the encoded structures would exceed
+    /// `MAX_ADV_LEN` bytes.
+    pub fn build(structures: &[AdStructure<'_>]) -> Result<([u8; MAX_ADV_LEN], usize), adapter::Error> {
+        let mut buf = [0_u8; MAX_ADV_LEN];
+        let mut len = 0_usize;
+        for structure in structures {
+            structure.write_to(&mut buf, &mut len)?;
+        }
+        Ok((buf, len))
+    }
+}
+
+/// Iterator over the [`AdStructure`]s encoded in an advertisement's raw payload (for example a
+/// [`ReportInfo`]'s `data`), as produced by [`parse_ad_structures`].
+pub struct AdStructureIter<'a> {
+    data: &'a [u8],
+}
+impl<'a> Iterator for AdStructureIter<'a> {
+    type Item = AdStructure<'a>;
+    fn next(&mut self) -> Option<AdStructure<'a>> {
+        loop {
+            let (&struct_len, rest) = self.data.split_first()?;
+            // A zero-length structure is used as padding to the end of the buffer; per spec it
+            // terminates parsing.
+            if struct_len == 0 {
+                self.data = &[];
+                return None;
+            }
+            let struct_len = struct_len as usize;
+            if rest.len() < struct_len {
+                // Truncated/malformed structure; nothing more can be parsed.
+                self.data = &[];
+                return None;
+            }
+            let (structure, remainder) = rest.split_at(struct_len);
+            self.data = remainder;
+            let (&ad_type, value) = match structure.split_first() {
+                Some(v) => v,
+                None => continue,
+            };
+            // A structure whose value doesn't match its type's expected shape (empty Flags/TX
+            // Power, non-UTF-8 name, ...) is surfaced as `Unknown` rather than aborting the
+            // whole iterator, so one malformed structure can't hide every structure after it.
+            return Some(match ad_type {
+                ad_type::FLAGS => match value.first() {
+                    Some(&flags) => AdStructure::Flags(flags),
+                    None => AdStructure::Unknown { ad_type, data: value },
+                },
+                ad_type::INCOMPLETE_SERVICE_UUID_16 => AdStructure::IncompleteServiceUUID16(value),
+                ad_type::COMPLETE_SERVICE_UUID_16 => AdStructure::CompleteServiceUUID16(value),
+                ad_type::INCOMPLETE_SERVICE_UUID_128 => AdStructure::IncompleteServiceUUID128(value),
+                ad_type::COMPLETE_SERVICE_UUID_128 => AdStructure::CompleteServiceUUID128(value),
+                ad_type::SHORTENED_LOCAL_NAME => match core::str::from_utf8(value) {
+                    Ok(name) => AdStructure::ShortenedLocalName(name),
+                    Err(_) => AdStructure::Unknown { ad_type, data: value },
+                },
+                ad_type::COMPLETE_LOCAL_NAME => match core::str::from_utf8(value) {
+                    Ok(name) => AdStructure::CompleteLocalName(name),
+                    Err(_) => AdStructure::Unknown { ad_type, data: value },
+                },
+                ad_type::TX_POWER_LEVEL => match value.first() {
+                    Some(&power) => AdStructure::TxPowerLevel(power as i8),
+                    None => AdStructure::Unknown { ad_type, data: value },
+                },
+                ad_type::MANUFACTURER_SPECIFIC_DATA if value.len() >= 2 => {
+                    AdStructure::ManufacturerSpecificData {
+                        company_id: u16::from_le_bytes([value[0], value[1]]),
+                        data: &value[2..],
+                    }
+                }
+                ad_type::SERVICE_DATA_16 if value.len() >= 2 => AdStructure::ServiceData16 {
+                    uuid: u16::from_le_bytes([value[0], value[1]]),
+                    data: &value[2..],
+                },
+                ad_type => AdStructure::Unknown { ad_type, data: value },
+            });
+        }
+    }
+}
+/// Parses a raw advertisement payload into its [`AdStructure`]s, borrowing from `data`.
+pub fn parse_ad_structures(data: &[u8]) -> AdStructureIter<'_> {
+    AdStructureIter { data }
+}
+
+/// LEMeta subevent code for the LE Connection Complete event.
+const CONNECTION_COMPLETE_SUBEVENT_CODE: u8 = 0x01;
+/// LEMeta subevent code for the LE Connection Update Complete event.
+const CONNECTION_UPDATE_COMPLETE_SUBEVENT_CODE: u8 = 0x03;
+
+/// Parameters for [`LEAdapter::connect`], wrapping the HCI LE Create Connection command's
+/// parameters. See the Core spec Vol 4, Part E 7.8.12 for the meaning of each field.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ConnectionParameters {
+    pub scan_interval: u16,
+    pub scan_window: u16,
+    pub initiator_filter_policy: ScanFilterPolicy,
+    pub conn_interval_min: u16,
+    pub conn_interval_max: u16,
+    pub conn_latency: u16,
+    pub supervision_timeout: u16,
+    pub min_ce_length: u16,
+    pub max_ce_length: u16,
+}
+
+/// Role a connected link is playing, decoded from the LE Connection Complete event's `Role`
+/// field.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Role {
+    Central,
+    Peripheral,
+}
+impl Role {
+    fn from_raw(raw: u8) -> Option<Role> {
+        match raw {
+            0x00 => Some(Role::Central),
+            0x01 => Some(Role::Peripheral),
+            _ => None,
+        }
+    }
+}
+
+/// A connection handle surfaced from an LE Connection Complete event: the connection handle
+/// used to address the link in later commands, the role taken on the link, the peer's address,
+/// and the negotiated connection interval/latency/supervision timeout.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Connection {
+    pub handle: u16,
+    pub role: Role,
+    pub peer_address_type: AddressType,
+    pub peer_address: BTAddress,
+    pub interval: u16,
+    pub latency: u16,
+    pub supervision_timeout: u16,
+}
+
+/// Events yielded by [`LEAdapter::connection_event_stream`] for a central application to manage
+/// link state with.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ConnectionEvent {
+    /// An LE Create Connection (or an incoming connection while advertising) completed.
+    Complete(Connection),
+    /// The connection parameters of an existing link were renegotiated.
+    UpdateComplete {
+        handle: u16,
+        interval: u16,
+        latency: u16,
+        supervision_timeout: u16,
+    },
+    /// A link was torn down.
+    Disconnected { handle: u16, reason: u8 },
+}
+
+/// Data-status bits (5-6) of an LE Extended Advertising Report's `event_type` field, indicating
+/// whether the accompanying data is all of the advertisement, or a fragment of a longer one.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum DataStatus {
+    Complete,
+    MoreToFollow,
+    Truncated,
+}
+impl DataStatus {
+    fn from_event_type(event_type: u16) -> Option<DataStatus> {
+        match (event_type >> 5) & 0b11 {
+            0b00 => Some(DataStatus::Complete),
+            0b01 => Some(DataStatus::MoreToFollow),
+            0b10 => Some(DataStatus::Truncated),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies an in-progress extended advertisement reassembly. Fragments of the same
+/// advertisement share both the advertiser's address and its advertising SID.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct FragmentKey {
+    address: BTAddress,
+    advertising_sid: Option<u8>,
+}
+
+/// A single reassembled extended advertisement, yielded once its key is flushed by a "complete"
+/// or "truncated" fragment (or by hitting the length cap).
+#[derive(Clone, Debug)]
+pub struct ExtendedReportInfo<Buf: Storage<u8>> {
+    pub address_type: AddressType,
+    pub address: BTAddress,
+    pub advertising_sid: Option<u8>,
+    pub rssi: Option<RSSI>,
+    pub data: Buf,
+    /// `true` if the controller reported this advertisement as truncated before the host could
+    /// reassemble all of its fragments.
+    pub truncated: bool,
+}
+
+struct FragmentEntry {
+    key: FragmentKey,
+    data: [u8; MAX_REASSEMBLED_LEN],
+    len: usize,
+}
+
+/// Bounded cache of in-progress extended advertisement reassemblies, keyed by
+/// `(advertiser_address, advertising_SID)`.
+struct FragmentReassembly {
+    entries: [Option<FragmentEntry>; MAX_REASSEMBLY_ENTRIES],
+}
+impl FragmentReassembly {
+    fn new() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+    fn index_of(&self, key: FragmentKey) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|e| matches!(e, Some(e) if e.key == key))
+    }
+    /// Finds the slot for `key`, reusing an empty one if the cache isn't full yet. If the cache
+    /// is full and `key` isn't already tracked, this evicts slot 0 unconditionally — entries
+    /// aren't age-ordered, so this is *not* an LRU/oldest-first eviction; a long-running
+    /// reassembly that happens to land in slot 0 is the one that keeps getting evicted.
+    fn slot_for(&mut self, key: FragmentKey) -> usize {
+        if let Some(index) = self.index_of(key) {
+            return index;
+        }
+        self.entries
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(0_usize)
+    }
+    /// Folds one LE Extended Advertising Report fragment into the cache, returning a finished
+    /// `ExtendedReportInfo` once a "complete" or "truncated" fragment closes out a key.
+    fn handle_fragment<Buf: Storage<u8> + Default + Extend<u8>>(
+        &mut self,
+        address_type: AddressType,
+        address: BTAddress,
+        advertising_sid: Option<u8>,
+        rssi: Option<RSSI>,
+        event_type: u16,
+        data: &[u8],
+    ) -> Option<ExtendedReportInfo<Buf>> {
+        let status = DataStatus::from_event_type(event_type)?;
+        let key = FragmentKey {
+            address,
+            advertising_sid,
+        };
+        let index = self.slot_for(key);
+        // `slot_for` may have handed back an occupied slot belonging to a different key (cache
+        // full, that slot evicted); drop it so we don't append onto the wrong advertiser.
+        if matches!(&self.entries[index], Some(entry) if entry.key != key) {
+            self.entries[index] = None;
+        }
+        let entry = self.entries[index].get_or_insert_with(|| FragmentEntry {
+            key,
+            data: [0_u8; MAX_REASSEMBLED_LEN],
+            len: 0,
+        });
+        let copy_len = data.len().min(MAX_REASSEMBLED_LEN - entry.len);
+        entry.data[entry.len..entry.len + copy_len].copy_from_slice(&data[..copy_len]);
+        entry.len += copy_len;
+        let truncated = status == DataStatus::Truncated || copy_len < data.len();
+        match status {
+            DataStatus::MoreToFollow if !truncated => None,
+            DataStatus::Complete | DataStatus::MoreToFollow | DataStatus::Truncated => {
+                let entry = self.entries[index].take()?;
+                let mut out = Buf::default();
+                out.extend(entry.data[..entry.len].iter().copied());
+                Some(ExtendedReportInfo {
+                    address_type,
+                    address,
+                    advertising_sid,
+                    rssi,
+                    data: out,
+                    truncated,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod fragment_reassembly_tests {
+    use super::{DataStatus, FragmentReassembly, ExtendedReportInfo, MAX_REASSEMBLED_LEN, MAX_REASSEMBLY_ENTRIES};
+    use crate::hci::le::report::AddressType;
+    use crate::BTAddress;
+
+    fn event_type(status: DataStatus) -> u16 {
+        (match status {
+            DataStatus::Complete => 0b00,
+            DataStatus::MoreToFollow => 0b01,
+            DataStatus::Truncated => 0b10,
+        }) << 5
+    }
+
+    fn address(byte: u8) -> BTAddress {
+        BTAddress::new([byte, 0, 0, 0, 0, 0])
+    }
+
+    fn handle(
+        reassembly: &mut FragmentReassembly,
+        addr: BTAddress,
+        status: DataStatus,
+        data: &[u8],
+    ) -> Option<ExtendedReportInfo<Vec<u8>>> {
+        reassembly.handle_fragment(
+            AddressType::Public,
+            addr,
+            None,
+            None,
+            event_type(status),
+            data,
+        )
+    }
+
+    #[test]
+    fn more_to_follow_then_complete_yields_concatenated_data() {
+        let mut reassembly = FragmentReassembly::new();
+        assert!(handle(&mut reassembly, address(1), DataStatus::MoreToFollow, &[1, 2, 3]).is_none());
+        let report = handle(&mut reassembly, address(1), DataStatus::Complete, &[4, 5]).unwrap();
+        assert_eq!(report.data, vec![1, 2, 3, 4, 5]);
+        assert!(!report.truncated);
+    }
+
+    #[test]
+    fn complete_clears_the_key_so_it_can_be_reused() {
+        let mut reassembly = FragmentReassembly::new();
+        handle(&mut reassembly, address(1), DataStatus::Complete, &[1]).unwrap();
+        // If the key weren't cleared, this would be seen as a continuation of the first report.
+        let report = handle(&mut reassembly, address(1), DataStatus::Complete, &[2]).unwrap();
+        assert_eq!(report.data, vec![2]);
+    }
+
+    #[test]
+    fn truncated_flushes_what_was_collected_and_marks_it_truncated() {
+        let mut reassembly = FragmentReassembly::new();
+        assert!(handle(&mut reassembly, address(1), DataStatus::MoreToFollow, &[1, 2]).is_none());
+        let report = handle(&mut reassembly, address(1), DataStatus::Truncated, &[3]).unwrap();
+        assert_eq!(report.data, vec![1, 2, 3]);
+        assert!(report.truncated);
+    }
+
+    #[test]
+    fn more_to_follow_past_the_length_cap_flushes_as_truncated() {
+        let mut reassembly = FragmentReassembly::new();
+        assert!(handle(
+            &mut reassembly,
+            address(1),
+            DataStatus::MoreToFollow,
+            &[0xAA; MAX_REASSEMBLED_LEN - 1]
+        )
+        .is_none());
+        // This fragment can't fully fit; the cap should flush what fits and mark it truncated
+        // rather than silently dropping the overflow or growing past the cap.
+        let report =
+            handle(&mut reassembly, address(1), DataStatus::MoreToFollow, &[0xBB; 10]).unwrap();
+        assert_eq!(report.data.len(), MAX_REASSEMBLED_LEN);
+        assert!(report.truncated);
+    }
+
+    #[test]
+    fn full_cache_evicts_a_slot_for_a_new_key() {
+        let mut reassembly = FragmentReassembly::new();
+        for i in 0..MAX_REASSEMBLY_ENTRIES as u8 {
+            assert!(
+                handle(&mut reassembly, address(i), DataStatus::MoreToFollow, &[i]).is_none()
+            );
+        }
+        // The cache is now full of `MAX_REASSEMBLY_ENTRIES` in-progress keys; a fragment for a
+        // brand new key must still be accepted (by evicting one of them) instead of silently
+        // merging into an unrelated advertiser's buffer.
+        let new_key_byte = MAX_REASSEMBLY_ENTRIES as u8;
+        assert!(handle(
+            &mut reassembly,
+            address(new_key_byte),
+            DataStatus::MoreToFollow,
+            &[new_key_byte]
+        )
+        .is_none());
+        let report = handle(
+            &mut reassembly,
+            address(new_key_byte),
+            DataStatus::Complete,
+            &[new_key_byte],
+        )
+        .unwrap();
+        assert_eq!(report.data, vec![new_key_byte, new_key_byte]);
+    }
+}
+
 pub struct LEAdapter<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> {
     adapter: Pin<S>,
 }
@@ -72,6 +724,38 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> LEAdapter<A, S> {
             .error()?;
         Ok(())
     }
+    /// Set extended advertisement scanning enable/disable. Controllers that support extended
+    /// advertising (LE Extended Advertising PHY/Report) are scanned with this instead of
+    /// [`LEAdapter::set_scan_enable`]. [`LEAdapter::set_extended_scan_parameters`] should be
+    /// called first.
+    pub async fn set_extended_scan_enable(
+        &mut self,
+        is_enabled: bool,
+        filter_duplicates: bool,
+    ) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::SetExtendedScanEnable {
+                is_enabled,
+                filter_duplicates,
+            })
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
+    /// Set extended advertisement scanning parameters. See
+    /// [`le::commands::SetExtendedScanParameters`] for more.
+    pub async fn set_extended_scan_parameters(
+        &mut self,
+        scan_parameters: ExtendedScanParameters,
+    ) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::SetExtendedScanParameters(scan_parameters))
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
     /// Enable or disable advertising. Make sure to set advertising parameters
     /// ([`LEAdapter::set_advertising_parameters`]) and advertising data
     /// ([`LEAdapter::set_advertising_data`]) before calling this function.
@@ -95,6 +779,89 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> LEAdapter<A, S> {
             .error()?;
         Ok(())
     }
+    /// Add a device to the controller's filter accept list (formerly "white list"). Has no
+    /// effect on devices already connected or already on the list. See
+    /// [`le::commands::AddDeviceToFilterAcceptList`] for more.
+    pub async fn add_device_to_filter_accept_list(
+        &mut self,
+        address_type: AddressType,
+        address: BTAddress,
+    ) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::AddDeviceToFilterAcceptList {
+                address_type,
+                address,
+            })
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
+    /// Remove a device from the controller's filter accept list. See
+    /// [`le::commands::RemoveDeviceFromFilterAcceptList`] for more.
+    pub async fn remove_device_from_filter_accept_list(
+        &mut self,
+        address_type: AddressType,
+        address: BTAddress,
+    ) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::RemoveDeviceFromFilterAcceptList {
+                address_type,
+                address,
+            })
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
+    /// Clear the controller's filter accept list. Only allowed while scanning, advertising and
+    /// initiating are all disabled.
+    pub async fn clear_filter_accept_list(&mut self) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::ClearFilterAcceptList {})
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
+    /// Read the total number of filter accept list entries the controller can store.
+    pub async fn read_filter_accept_list_size(&mut self) -> Result<u8, adapter::Error> {
+        let r = self
+            .adapter_mut()
+            .send_command(le::commands::ReadFilterAcceptListSize {})
+            .await?;
+        r.status.error()?;
+        Ok(r.filter_accept_list_size)
+    }
+    /// Initiate a connection to `peer_address`. Use [`LEAdapter::connect_cancel`] to abort
+    /// before the controller reports [`ConnectionEvent::Complete`] on
+    /// [`LEAdapter::connection_event_stream`]. See [`le::commands::LECreateConnection`] for more.
+    pub async fn connect(
+        &mut self,
+        peer_address_type: AddressType,
+        peer_address: BTAddress,
+        parameters: ConnectionParameters,
+    ) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::LECreateConnection {
+                peer_address_type,
+                peer_address,
+                parameters,
+            })
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
+    /// Cancel a connection attempt started by [`LEAdapter::connect`] that has not yet completed.
+    pub async fn connect_cancel(&mut self) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::CreateConnectionCancel {})
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
     /// Get `RAND_LEN` (8) bytes from the HCI Controller.
     pub async fn get_rand(&mut self) -> Result<[u8; RAND_LEN], adapter::Error> {
         let r = self
@@ -104,6 +871,29 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> LEAdapter<A, S> {
         r.status.error()?;
         Ok(r.random_bytes)
     }
+    /// Set the controller's random device address. Combine with [`OwnAddressType::Random`] on
+    /// [`AdvertisingParameters`]/[`ScanParameters`] to advertise/scan using it instead of the
+    /// public device address.
+    pub async fn set_random_address(&mut self, address: BTAddress) -> Result<(), adapter::Error> {
+        self.adapter_mut()
+            .send_command(le::commands::SetRandomAddress { address })
+            .await?
+            .status
+            .error()?;
+        Ok(())
+    }
+    /// Draws entropy from the controller via [`LEAdapter::get_rand`] and derives a static random
+    /// address from it: the two most significant bits are set to `0b11`, marking it as a static
+    /// (rather than private resolvable/non-resolvable) random address per the Core spec. This is
+    /// the recommended default identity for peripherals without an assigned public address; pass
+    /// the result to [`LEAdapter::set_random_address`] to program it.
+    pub async fn generate_static_random_address(&mut self) -> Result<BTAddress, adapter::Error> {
+        let random = self.get_rand().await?;
+        let mut address = [0_u8; 6];
+        address.copy_from_slice(&random[..6]);
+        address[5] |= STATIC_RANDOM_ADDRESS_MARKER;
+        Ok(BTAddress::new(address))
+    }
     /// Set advertising data (0-31 bytes).
     /// # Errors
     /// Returns `adapter::Error::BadParameter` if `data.len() > MAX_ADV_LEN` (31).
@@ -118,6 +908,18 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> LEAdapter<A, S> {
             .error()?;
         Ok(())
     }
+    /// Set advertising data from typed [`AdStructure`]s instead of a raw byte slice. See
+    /// [`AdvertisingData::build`] for the serialization this performs.
+    /// # Errors
+    /// Returns `adapter::Error::BadParameter` if the encoded structures would exceed
+    /// `MAX_ADV_LEN` bytes.
+    pub async fn set_advertising_data_structures(
+        &mut self,
+        structures: &[AdStructure<'_>],
+    ) -> Result<(), adapter::Error> {
+        let (buf, len) = AdvertisingData::build(structures)?;
+        self.set_advertising_data(&buf[..len]).await
+    }
     /*
     /// BLE Advertisement Stream. Returns advertising reports [`ReportInfo'] that contain
     /// advertisement type [`EventType`], address type [`AddressType`], bluetooth address [`BTAddress`],
@@ -172,19 +974,45 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> LEAdapter<A, S> {
         crate::asyncs::stream::unfold((self, None), f)
     }
     */
+    /// HCI event stream, optionally restricted to a [`HciFilter`]. The filter is programmed onto
+    /// the underlying transport once (via [`adapter::Adapter::set_event_filter`]) before the
+    /// first event is read, and is also applied host-side to each event, so transports that
+    /// can't filter at the kernel level still only yield matching events.
     pub fn hci_event_stream<'a, 'b: 'a, Buf: Storage<u8> + 'b>(
         &'a mut self,
+        filter: Option<HciFilter>,
     ) -> impl Stream<Item = Result<EventPacket<Buf>, adapter::Error>> + 'a {
-        todo!("set HCI Filter to AdvertisingReport");
-        futures_util::stream::unfold(self, move |s| async move {
-            Some((s.adapter.as_mut().read_event().await, s))
-        })
+        futures_util::stream::unfold(
+            (self, filter, false),
+            move |(s, filter, programmed)| async move {
+                if !programmed {
+                    if let Some(filter) = filter {
+                        if let Err(e) = s.adapter_mut().set_event_filter(filter).await {
+                            return Some((Err(e), (s, Some(filter), false)));
+                        }
+                    }
+                }
+                loop {
+                    match s.adapter.as_mut().read_event().await {
+                        Ok(event) => {
+                            if filter.map_or(true, |f| f.matches(&event)) {
+                                return Some((Ok(event), (s, filter, true)));
+                            }
+                        }
+                        Err(e) => return Some((Err(e), (s, filter, true))),
+                    }
+                }
+            },
+        )
     }
 
     pub fn advertising_report_stream<'a, 'b: 'a, Buf: Storage<ReportInfo<StaticAdvBuffer>> + 'b>(
         &'a mut self,
     ) -> impl Stream<Item = Result<AdvertisingReport<Buf>, adapter::Error>> + 'a {
-        self.hci_event_stream().filter_map(
+        self.hci_event_stream(Some(
+            HciFilter::new().allow_le_meta_subevent(ADVERTISING_REPORT_SUBEVENT_CODE),
+        ))
+        .filter_map(
             |p: Result<EventPacket<Box<[u8]>>, adapter::Error>| async move {
                 let event = match p {
                     Ok(event) => event,
@@ -225,6 +1053,121 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> LEAdapter<A, S> {
             )
             .flatten()
     }
+    /// Extended advertisement stream. Decodes LE Extended Advertising Report subevents (subevent
+    /// code `0x0D`) and reassembles advertisements fragmented across multiple HCI events (data
+    /// status "more-to-follow") into a single [`ExtendedReportInfo`] per advertiser/SID, so
+    /// callers can receive advertisements longer than the 31-byte legacy limit. A fragment cache
+    /// bounded to [`MAX_REASSEMBLY_ENTRIES`] keys and [`MAX_REASSEMBLED_LEN`] bytes per key
+    /// protects against unbounded memory growth if a "complete" or "truncated" fragment never
+    /// arrives for a key.
+    pub fn extended_advertisement_stream<'a, Buf: Storage<u8> + Default + Extend<u8> + 'a>(
+        &'a mut self,
+    ) -> impl Stream<Item = Result<ExtendedReportInfo<Buf>, adapter::Error>> + 'a {
+        let mut reassembly = FragmentReassembly::new();
+        self.hci_event_stream::<Box<[u8]>>(Some(
+            HciFilter::new().allow_le_meta_subevent(EXTENDED_ADVERTISING_REPORT_SUBEVENT_CODE),
+        ))
+        .filter_map(move |p| {
+            let result = (|| -> Result<Option<ExtendedReportInfo<Buf>>, adapter::Error> {
+                let event = p?;
+                if event.event_code != EventCode::LEMeta {
+                    return Ok(None);
+                }
+                let meta_event = RawMetaEvent::try_from(event.as_ref())
+                    .map_err(StreamError::EventError)
+                    .map_err(adapter::Error::StreamError)?;
+                if meta_event.subevent_code() != EXTENDED_ADVERTISING_REPORT_SUBEVENT_CODE {
+                    return Ok(None);
+                }
+                let report = le::report::ExtendedAdvertisingReport::meta_unpack_packet(
+                    meta_event.as_ref(),
+                )
+                .map_err(StreamError::EventError)
+                .map_err(adapter::Error::StreamError)?;
+                Ok(reassembly.handle_fragment(
+                    report.address_type,
+                    report.address,
+                    report.advertising_sid,
+                    report.rssi,
+                    report.event_type,
+                    report.data.as_ref(),
+                ))
+            })();
+            async move { result.transpose() }
+        })
+    }
+    /// Connection lifecycle stream for a GAP central: yields
+    /// [`ConnectionEvent::Complete`]/[`ConnectionEvent::UpdateComplete`] (decoded from LEMeta
+    /// subevents) and [`ConnectionEvent::Disconnected`] (decoded from the HCI Disconnection
+    /// Complete event) so an application can track link state without polling.
+    pub fn connection_event_stream<'a>(
+        &'a mut self,
+    ) -> impl Stream<Item = Result<ConnectionEvent, adapter::Error>> + 'a {
+        futures_util::stream::unfold(self, move |s| async move {
+            let event: Result<EventPacket<Box<[u8]>>, adapter::Error> =
+                s.adapter.as_mut().read_event().await;
+            let result = (|| -> Result<Option<ConnectionEvent>, adapter::Error> {
+                let event = event?;
+                match event.event_code {
+                    EventCode::LEMeta => {
+                        let meta_event = RawMetaEvent::try_from(event.as_ref())
+                            .map_err(StreamError::EventError)
+                            .map_err(adapter::Error::StreamError)?;
+                        match meta_event.subevent_code() {
+                            CONNECTION_COMPLETE_SUBEVENT_CODE => {
+                                let complete = le::events::ConnectionComplete::meta_unpack_packet(
+                                    meta_event.as_ref(),
+                                )
+                                .map_err(StreamError::EventError)
+                                .map_err(adapter::Error::StreamError)?;
+                                // An unrecognized role byte means this controller speaks a newer
+                                // spec revision than we do; drop the event rather than error.
+                                Ok(Role::from_raw(complete.role).map(|role| {
+                                    ConnectionEvent::Complete(Connection {
+                                        handle: complete.connection_handle,
+                                        role,
+                                        peer_address_type: complete.peer_address_type,
+                                        peer_address: complete.peer_address,
+                                        interval: complete.conn_interval,
+                                        latency: complete.conn_latency,
+                                        supervision_timeout: complete.supervision_timeout,
+                                    })
+                                }))
+                            }
+                            CONNECTION_UPDATE_COMPLETE_SUBEVENT_CODE => {
+                                let update =
+                                    le::events::ConnectionUpdateComplete::meta_unpack_packet(
+                                        meta_event.as_ref(),
+                                    )
+                                    .map_err(StreamError::EventError)
+                                    .map_err(adapter::Error::StreamError)?;
+                                Ok(Some(ConnectionEvent::UpdateComplete {
+                                    handle: update.connection_handle,
+                                    interval: update.conn_interval,
+                                    latency: update.conn_latency,
+                                    supervision_timeout: update.supervision_timeout,
+                                }))
+                            }
+                            _ => Ok(None),
+                        }
+                    }
+                    EventCode::DisconnectionComplete => {
+                        let disconnect =
+                            crate::hci::events::DisconnectionComplete::unpack_event_packet(&event)
+                                .map_err(StreamError::CommandError)
+                                .map_err(adapter::Error::StreamError)?;
+                        Ok(Some(ConnectionEvent::Disconnected {
+                            handle: disconnect.connection_handle,
+                            reason: disconnect.reason,
+                        }))
+                    }
+                    _ => Ok(None),
+                }
+            })();
+            Some((result.transpose(), s))
+        })
+        .filter_map(|r| async move { r })
+    }
 }
 
 impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> Advertiser for LEAdapter<A, S> {
@@ -252,3 +1195,76 @@ impl<A: adapter::Adapter, S: Deref<Target = A> + DerefMut> Advertiser for LEAdap
         Box::pin(LEAdapter::set_advertising_data(self, data))
     }
 }
+
+#[cfg(test)]
+mod ad_structure_tests {
+    use super::{parse_ad_structures, AdStructure, AdvertisingData};
+    use crate::hci::adapter;
+
+    #[test]
+    fn round_trips_through_build_and_parse() {
+        let structures = [
+            AdStructure::Flags(0x06),
+            AdStructure::CompleteLocalName("btle"),
+            AdStructure::TxPowerLevel(-8),
+            AdStructure::ManufacturerSpecificData {
+                company_id: 0x1234,
+                data: &[0xAA, 0xBB],
+            },
+        ];
+        let (buf, len) = AdvertisingData::build(&structures).unwrap();
+        assert!(parse_ad_structures(&buf[..len]).eq(structures.iter().copied()));
+    }
+
+    #[test]
+    fn build_rejects_payload_over_max_adv_len() {
+        let long_name = "this local name is far too long to fit in 31 bytes of AD data";
+        let structures = [AdStructure::CompleteLocalName(long_name)];
+        assert_eq!(
+            AdvertisingData::build(&structures).unwrap_err(),
+            adapter::Error::BadParameter
+        );
+    }
+
+    #[test]
+    fn parse_stops_at_zero_length_padding() {
+        // A real structure, followed by zero-length padding, followed by a second real
+        // structure that must never be reached.
+        let data = [
+            0x02, 0x01, 0x06, // Flags(0x06)
+            0x00, // padding: terminates parsing
+            0x02, 0x0A, 0x00, // TxPowerLevel(0) -- must not be yielded
+        ];
+        let mut parsed = parse_ad_structures(&data);
+        assert_eq!(parsed.next(), Some(AdStructure::Flags(0x06)));
+        assert_eq!(parsed.next(), None);
+    }
+
+    #[test]
+    fn parse_falls_back_to_unknown_on_non_utf8_name() {
+        let data = [0x03, 0x09, 0xFF, 0xFE]; // Complete Local Name, invalid UTF-8
+        let mut parsed = parse_ad_structures(&data);
+        assert_eq!(
+            parsed.next(),
+            Some(AdStructure::Unknown {
+                ad_type: 0x09,
+                data: &[0xFF, 0xFE],
+            })
+        );
+        assert_eq!(parsed.next(), None);
+    }
+
+    #[test]
+    fn parse_falls_back_to_unknown_on_undersized_manufacturer_data() {
+        let data = [0x02, 0xFF, 0x01]; // Manufacturer Specific Data, missing the company ID
+        let mut parsed = parse_ad_structures(&data);
+        assert_eq!(
+            parsed.next(),
+            Some(AdStructure::Unknown {
+                ad_type: 0xFF,
+                data: &[0x01],
+            })
+        );
+        assert_eq!(parsed.next(), None);
+    }
+}
@@ -0,0 +1,39 @@
+use crate::hci::adapter;
+use crate::hci::adapters::le::{AdvertisingFilterPolicy, OwnAddressType};
+use crate::BoxFuture;
+
+/// The kind of advertising PDU broadcast. See the Core spec Vol 4, Part E 7.8.5.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AdvertisingType {
+    ConnectableUndirected,
+    ConnectableDirected,
+    ScannableUndirected,
+    NonConnectableUndirected,
+}
+
+/// Parameters for legacy LE advertising. See
+/// [`crate::hci::adapters::le::LEAdapter::set_advertising_parameters`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AdvertisingParameters {
+    pub advertising_interval_min: u16,
+    pub advertising_interval_max: u16,
+    pub advertising_type: AdvertisingType,
+    /// Which address the controller advertises as its own. See [`OwnAddressType`].
+    pub own_address_type: OwnAddressType,
+    /// Restricts which peers may scan/connect to this advertiser. See [`AdvertisingFilterPolicy`].
+    pub filter_policy: AdvertisingFilterPolicy,
+}
+
+/// Common advertiser operations, implemented by [`crate::hci::adapters::le::LEAdapter`] so
+/// callers can depend on advertising behavior without naming a concrete adapter type.
+pub trait Advertiser {
+    fn set_advertising_enable(&mut self, is_enabled: bool) -> BoxFuture<Result<(), adapter::Error>>;
+    fn set_advertising_parameters(
+        &mut self,
+        advertisement_parameters: AdvertisingParameters,
+    ) -> BoxFuture<Result<(), adapter::Error>>;
+    fn set_advertising_data<'s, 'b: 's>(
+        &'b mut self,
+        data: &'s [u8],
+    ) -> BoxFuture<'s, Result<(), adapter::Error>>;
+}
@@ -0,0 +1,33 @@
+use crate::hci::adapters::le::{OwnAddressType, ScanFilterPolicy};
+
+/// Whether the scanner sends scan requests (`Active`) or only listens (`Passive`).
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ScanType {
+    Passive,
+    Active,
+}
+
+/// Parameters for legacy LE scanning. See [`crate::hci::adapters::le::LEAdapter::set_scan_parameters`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ScanParameters {
+    pub scan_type: ScanType,
+    pub scan_interval: u16,
+    pub scan_window: u16,
+    /// Which address the controller reports as its own while scanning. See [`OwnAddressType`].
+    pub own_address_type: OwnAddressType,
+    /// Restricts which advertisers the controller reports. See [`ScanFilterPolicy`].
+    pub filter_policy: ScanFilterPolicy,
+}
+
+/// Parameters for LE extended scanning. See
+/// [`crate::hci::adapters::le::LEAdapter::set_extended_scan_parameters`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ExtendedScanParameters {
+    pub scan_type: ScanType,
+    pub scan_interval: u16,
+    pub scan_window: u16,
+    /// Which address the controller reports as its own while scanning. See [`OwnAddressType`].
+    pub own_address_type: OwnAddressType,
+    /// Restricts which advertisers the controller reports. See [`ScanFilterPolicy`].
+    pub filter_policy: ScanFilterPolicy,
+}